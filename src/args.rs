@@ -0,0 +1,28 @@
+use clap::Parser;
+
+/// CLI/env configuration for the CDC consumer, so it can be pointed at a
+/// different cluster or table without a rebuild.
+#[derive(Debug, Parser)]
+#[command(author, version, about = "Debezium CDC consumer")]
+pub struct Args {
+    /// Kafka/Redpanda bootstrap servers, e.g. "localhost:19092".
+    #[arg(long, env = "CDC_BROKERS", default_value = "localhost:19092")]
+    pub brokers: String,
+
+    /// CDC topic to subscribe to. Repeat the flag for multiple topics.
+    #[arg(long = "topic", env = "CDC_TOPICS", value_delimiter = ',', default_value = "shori_data.ShoriDB.dbo.Users")]
+    pub topics: Vec<String>,
+
+    /// Consumer group id.
+    #[arg(long, env = "CDC_GROUP_ID", default_value = "shori-consumer")]
+    pub group_id: String,
+
+    /// Where to start reading when no committed offset exists ("earliest" or "latest").
+    #[arg(long, env = "CDC_OFFSET_RESET", default_value = "earliest")]
+    pub offset_reset: String,
+
+    /// Optional topic to republish normalized change events to. When unset,
+    /// the consumer only logs events and never produces downstream.
+    #[arg(long, env = "CDC_SINK_TOPIC")]
+    pub sink_topic: Option<String>,
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The kind of change a Debezium envelope describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Create,
+    Update,
+    Delete,
+    Snapshot,
+}
+
+impl Operation {
+    fn from_op_code(op: &str) -> Result<Self, CdcError> {
+        match op {
+            "c" => Ok(Operation::Create),
+            "u" => Ok(Operation::Update),
+            "d" => Ok(Operation::Delete),
+            "r" => Ok(Operation::Snapshot),
+            other => Err(CdcError::UnknownOp(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Operation::Create => "create",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+            Operation::Snapshot => "snapshot",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Metadata Debezium attaches to every change event: where it came from and when.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Source {
+    pub ts_ms: i64,
+    pub db: String,
+    pub table: String,
+    #[serde(default)]
+    pub lsn: Option<i64>,
+    #[serde(default)]
+    pub scn: Option<i64>,
+}
+
+impl Source {
+    /// The source database's log position for this change, if the connector reports one
+    /// (SQL Server/Postgres use an LSN, Oracle uses an SCN).
+    pub fn log_position(&self) -> Option<i64> {
+        self.lsn.or(self.scn)
+    }
+}
+
+/// A decoded Debezium change event, independent of the Kafka/Debezium wire format.
+#[derive(Debug, Clone)]
+pub struct ChangeEvent {
+    pub op: Operation,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub source: Source,
+    pub ts_ms: i64,
+}
+
+impl ChangeEvent {
+    /// Columns present in `after` but absent or different in `before`.
+    ///
+    /// For creates/snapshots (no `before`), every column in `after` counts as changed.
+    pub fn changed_columns(&self) -> Vec<String> {
+        let (Some(after), before) = (&self.after, &self.before) else {
+            return Vec::new();
+        };
+        let Some(after_obj) = after.as_object() else {
+            return Vec::new();
+        };
+
+        let before_obj = before.as_ref().and_then(Value::as_object);
+
+        after_obj
+            .iter()
+            .filter(|(k, v)| match before_obj.and_then(|b| b.get(*k)) {
+                Some(old) => old != *v,
+                None => true,
+            })
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    payload: Payload,
+}
+
+#[derive(Debug, Deserialize)]
+struct Payload {
+    op: String,
+    before: Option<Value>,
+    after: Option<Value>,
+    source: Source,
+    ts_ms: i64,
+}
+
+/// Errors that can occur while decoding a Debezium CDC message.
+#[derive(Debug)]
+pub enum CdcError {
+    InvalidJson(serde_json::Error),
+    UnknownOp(String),
+}
+
+impl fmt::Display for CdcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CdcError::InvalidJson(e) => write!(f, "invalid Debezium envelope: {e}"),
+            CdcError::UnknownOp(op) => write!(f, "unknown Debezium op code: {op}"),
+        }
+    }
+}
+
+impl std::error::Error for CdcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CdcError::InvalidJson(e) => Some(e),
+            CdcError::UnknownOp(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for CdcError {
+    fn from(e: serde_json::Error) -> Self {
+        CdcError::InvalidJson(e)
+    }
+}
+
+/// Decode a raw Debezium CDC message payload into a typed [`ChangeEvent`].
+pub fn parse_debezium(payload: &str) -> Result<ChangeEvent, CdcError> {
+    let envelope: Envelope = serde_json::from_str(payload)?;
+    let Payload {
+        op,
+        before,
+        after,
+        source,
+        ts_ms,
+    } = envelope.payload;
+
+    Ok(ChangeEvent {
+        op: Operation::from_op_code(&op)?,
+        before,
+        after,
+        source,
+        ts_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(op: &str, before: &str, after: &str, source: &str) -> String {
+        format!(
+            r#"{{"payload":{{"op":"{op}","before":{before},"after":{after},"source":{source},"ts_ms":1700000000123}}}}"#
+        )
+    }
+
+    #[test]
+    fn parses_an_update_with_lsn() {
+        let payload = envelope(
+            "u",
+            r#"{"id":1,"name":"old"}"#,
+            r#"{"id":1,"name":"new"}"#,
+            r#"{"ts_ms":1700000000000,"db":"ShoriDB","table":"Users","lsn":12345}"#,
+        );
+
+        let event = parse_debezium(&payload).expect("valid envelope should parse");
+
+        assert_eq!(event.op, Operation::Update);
+        assert_eq!(event.source.db, "ShoriDB");
+        assert_eq!(event.source.table, "Users");
+        assert_eq!(event.source.lsn, Some(12345));
+        assert_eq!(event.source.scn, None);
+        assert_eq!(event.changed_columns(), vec!["name"]);
+    }
+
+    #[test]
+    fn parses_a_snapshot_with_scn_and_no_before() {
+        let payload = envelope(
+            "r",
+            "null",
+            r#"{"id":1,"name":"new"}"#,
+            r#"{"ts_ms":1700000000000,"db":"ShoriDB","table":"Users","scn":99}"#,
+        );
+
+        let event = parse_debezium(&payload).expect("valid envelope should parse");
+
+        assert_eq!(event.op, Operation::Snapshot);
+        assert_eq!(event.source.scn, Some(99));
+        assert!(event.before.is_none());
+
+        let mut changed = event.changed_columns();
+        changed.sort();
+        assert_eq!(changed, vec!["id", "name"]);
+    }
+
+    #[test]
+    fn log_position_prefers_lsn_over_scn() {
+        let payload = envelope(
+            "u",
+            r#"{"id":1}"#,
+            r#"{"id":1}"#,
+            r#"{"ts_ms":1700000000000,"db":"ShoriDB","table":"Users","lsn":5,"scn":9}"#,
+        );
+
+        let event = parse_debezium(&payload).expect("valid envelope should parse");
+        assert_eq!(event.source.log_position(), Some(5));
+    }
+
+    #[test]
+    fn rejects_an_unknown_op_code() {
+        let payload = envelope(
+            "x",
+            "null",
+            r#"{"id":1}"#,
+            r#"{"ts_ms":1700000000000,"db":"ShoriDB","table":"Users"}"#,
+        );
+
+        let err = parse_debezium(&payload).expect_err("unknown op should be rejected");
+        assert!(matches!(err, CdcError::UnknownOp(op) if op == "x"));
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        let err = parse_debezium("not json").expect_err("malformed payload should be rejected");
+        assert!(matches!(err, CdcError::InvalidJson(_)));
+    }
+
+    #[test]
+    fn changed_columns_is_empty_when_after_is_missing() {
+        let payload = envelope(
+            "d",
+            r#"{"id":1,"name":"old"}"#,
+            "null",
+            r#"{"ts_ms":1700000000000,"db":"ShoriDB","table":"Users"}"#,
+        );
+
+        let event = parse_debezium(&payload).expect("valid envelope should parse");
+        assert_eq!(event.op, Operation::Delete);
+        assert!(event.changed_columns().is_empty());
+    }
+}
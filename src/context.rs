@@ -0,0 +1,73 @@
+use std::sync::{Arc, OnceLock, Weak};
+
+use rdkafka::client::ClientContext;
+use rdkafka::consumer::stream_consumer::StreamConsumer;
+use rdkafka::consumer::{CommitMode, Consumer, ConsumerContext, Rebalance};
+use rdkafka::error::KafkaResult;
+use rdkafka::topic_partition_list::{TopicPartitionList, TopicPartitionListElem};
+
+/// Consumer/client context that logs rebalances and commits offsets
+/// synchronously before partitions are revoked, so a scaling consumer
+/// group never double-processes a message after a rebalance.
+///
+/// `ConsumerContext` callbacks only get the raw `TopicPartitionList` handed
+/// to the rebalance (whose offsets are `OFFSET_INVALID`, not the consumer's
+/// actual positions), so committing it directly would be a no-op. Instead
+/// the context holds a weak handle to the consumer, registered right after
+/// creation via [`CdcContext::set_consumer`], and commits the consumer's
+/// real state through it.
+#[derive(Clone, Default)]
+pub struct CdcContext {
+    consumer: Arc<OnceLock<Weak<StreamConsumer<CdcContext>>>>,
+}
+
+impl CdcContext {
+    /// Register the consumer this context belongs to, so rebalance
+    /// callbacks can commit its current offsets. Must be called once,
+    /// before the consumer subscribes (rebalances can't fire before then).
+    pub fn set_consumer(&self, consumer: &Arc<StreamConsumer<CdcContext>>) {
+        self.consumer
+            .set(Arc::downgrade(consumer))
+            .unwrap_or_else(|_| panic!("CdcContext::set_consumer called more than once"));
+    }
+}
+
+impl ClientContext for CdcContext {}
+
+impl ConsumerContext for CdcContext {
+    fn pre_rebalance(&self, rebalance: &Rebalance<'_>) {
+        if let Rebalance::Revoke(tpl) = rebalance {
+            log_partitions("Revoking", tpl);
+
+            match self.consumer.get().and_then(Weak::upgrade) {
+                Some(consumer) => {
+                    if let Err(e) = consumer.commit_consumer_state(CommitMode::Sync) {
+                        eprintln!("Failed to commit offsets before rebalance: {e}");
+                    }
+                }
+                None => eprintln!("No consumer registered, skipping pre-rebalance commit"),
+            }
+        }
+    }
+
+    fn post_rebalance(&self, rebalance: &Rebalance<'_>) {
+        if let Rebalance::Assign(tpl) = rebalance {
+            log_partitions("Assigned", tpl);
+        }
+    }
+
+    fn commit_callback(&self, result: KafkaResult<()>, _offsets: &TopicPartitionList) {
+        if let Err(e) = result {
+            eprintln!("Commit callback reported an error: {e}");
+        }
+    }
+}
+
+fn log_partitions(action: &str, tpl: &TopicPartitionList) {
+    let parts: Vec<String> = tpl
+        .elements()
+        .iter()
+        .map(|e: &TopicPartitionListElem| format!("{}:{}", e.topic(), e.partition()))
+        .collect();
+    println!("{action} partitions: [{}]", parts.join(", "));
+}
@@ -0,0 +1,120 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+
+/// How often the lag tracker prints percentile/throughput stats.
+pub const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tracks end-to-end CDC lag (now - source commit time) across messages and
+/// periodically reports percentiles and throughput.
+pub struct LagTracker {
+    histogram: Mutex<Histogram<u64>>,
+    messages_since_report: Mutex<u64>,
+}
+
+impl LagTracker {
+    /// Tracks lag from 1ms up to one hour, with 3 significant digits of precision.
+    pub fn new() -> Self {
+        let histogram =
+            Histogram::new_with_bounds(1, Duration::from_secs(3600).as_millis() as u64, 3)
+                .expect("invalid histogram bounds");
+        Self {
+            histogram: Mutex::new(histogram),
+            messages_since_report: Mutex::new(0),
+        }
+    }
+
+    /// Record the lag, in milliseconds, for one consumed message.
+    pub fn record(&self, lag_ms: u64) {
+        let mut histogram = self.histogram.lock().expect("lag histogram lock poisoned");
+        if let Err(e) = histogram.record(lag_ms) {
+            eprintln!("Failed to record lag sample: {e}");
+        }
+        *self
+            .messages_since_report
+            .lock()
+            .expect("message counter lock poisoned") += 1;
+    }
+
+    /// Print p50/p90/p99/max lag and throughput since the last report, then reset the counters.
+    pub fn report(&self) {
+        let mut histogram = self.histogram.lock().expect("lag histogram lock poisoned");
+        let mut count = self
+            .messages_since_report
+            .lock()
+            .expect("message counter lock poisoned");
+
+        let throughput = *count as f64 / REPORT_INTERVAL.as_secs_f64();
+        println!(
+            "lag_ms p50={} p90={} p99={} max={} throughput={:.1} msg/s",
+            histogram.value_at_quantile(0.50),
+            histogram.value_at_quantile(0.90),
+            histogram.value_at_quantile(0.99),
+            histogram.max(),
+            throughput,
+        );
+
+        histogram.reset();
+        *count = 0;
+    }
+}
+
+impl Default for LagTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_samples_within_bounds() {
+        let tracker = LagTracker::new();
+        tracker.record(10);
+        tracker.record(20);
+        tracker.record(30);
+
+        let histogram = tracker.histogram.lock().unwrap();
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram.max(), 30);
+        assert_eq!(*tracker.messages_since_report.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn zero_lag_does_not_panic() {
+        let tracker = LagTracker::new();
+        tracker.record(0);
+
+        // Below the histogram's configured lowest discernible value (1ms),
+        // but 0 still maps to the first bucket, so hdrhistogram records it
+        // rather than rejecting it: it must be counted, without panicking.
+        let histogram = tracker.histogram.lock().unwrap();
+        assert_eq!(histogram.len(), 1);
+        assert_eq!(*tracker.messages_since_report.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn lag_beyond_the_tracked_range_does_not_panic() {
+        let tracker = LagTracker::new();
+        let one_hour_ms = Duration::from_secs(3600).as_millis() as u64;
+        tracker.record(one_hour_ms + 1);
+
+        let histogram = tracker.histogram.lock().unwrap();
+        assert_eq!(histogram.len(), 1);
+        assert_eq!(*tracker.messages_since_report.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn report_resets_histogram_and_counter() {
+        let tracker = LagTracker::new();
+        tracker.record(50);
+        tracker.report();
+
+        let histogram = tracker.histogram.lock().unwrap();
+        assert_eq!(histogram.len(), 0);
+        assert_eq!(*tracker.messages_since_report.lock().unwrap(), 0);
+    }
+}
@@ -1,77 +1,252 @@
+use std::sync::Arc;
 use std::time::Duration;
 
+use clap::Parser;
 use rdkafka::config::ClientConfig;
-use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
 use rdkafka::error::{KafkaError, RDKafkaErrorCode};
-use rdkafka::message::Message;
+use rdkafka::message::{BorrowedMessage, Message};
 
-const BROKER: &str = "localhost:19092";
-const TOPICS: &[&str] = &["shori_data.ShoriDB.dbo.Users"];
+mod args;
+mod cdc;
+mod context;
+mod latency;
+mod sink;
+
+use args::Args;
+use cdc::parse_debezium;
+use context::CdcContext;
+use latency::LagTracker;
+use sink::Sink;
 
 const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
 const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
-#[tokio::main]
-async fn main() {
-    println!("Connecting to {BROKER}...");
-
-    let consumer: StreamConsumer = ClientConfig::new()
-        .set("bootstrap.servers", BROKER)
-        .set("group.id", "shori-consumer")
-        .set("auto.offset.reset", "earliest")
-        .set("enable.auto.commit", "true")
-        .create()
-        .expect("Failed to create consumer");
-
-    consumer
-        .subscribe(TOPICS)
-        .expect("Failed to subscribe to topics");
+/// How often to force a synchronous commit, as a safety net on top of the
+/// async commits issued after every successfully processed message.
+const SYNC_COMMIT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The result of handling one consumed message.
+///
+/// Only [`ProcessOutcome::Processed`] advances the committed offset; a
+/// [`ProcessOutcome::Failed`] message is left uncommitted so the broker
+/// redelivers it.
+enum ProcessOutcome {
+    Processed,
+    Failed,
+}
 
-    println!("Subscribed to: {}", TOPICS.join(", "));
-    println!("Waiting for CDC events...\n");
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before UNIX epoch")
+        .as_millis() as i64
+}
 
-    let mut backoff = INITIAL_BACKOFF;
+/// Parse and log a single CDC message, returning whether it was handled.
+///
+/// Also records end-to-end lag into `lag_tracker`, preferring the
+/// Debezium `source.ts_ms` and falling back to the Kafka message
+/// timestamp when the envelope couldn't be parsed. When `sink` is set,
+/// the parsed event is forwarded downstream before the message is
+/// considered processed, so the source offset is only committed once
+/// the reshaped record has actually been produced.
+async fn handle_message(
+    msg: &BorrowedMessage<'_>,
+    lag_tracker: &LagTracker,
+    sink: Option<&Sink>,
+) -> ProcessOutcome {
+    let topic = msg.topic();
+    let partition = msg.partition();
+    let offset = msg.offset();
+
+    let payload = match msg.payload_view::<str>().and_then(|r| r.ok()) {
+        Some(p) => p,
+        None => {
+            eprintln!("[{topic}  p:{partition}  o:{offset}] message has no payload");
+            return ProcessOutcome::Failed;
+        }
+    };
+
+    println!("[{topic}  p:{partition}  o:{offset}]");
+
+    match parse_debezium(payload) {
+        Ok(event) => {
+            println!(
+                "op={} db={} table={} lsn/scn={:?} columns={:?}",
+                event.op,
+                event.source.db,
+                event.source.table,
+                event.source.log_position(),
+                event.changed_columns()
+            );
+
+            let lag_ms = (now_ms() - event.source.ts_ms).max(0) as u64;
+            lag_tracker.record(lag_ms);
+
+            if let Some(sink) = sink {
+                let key = msg.key_view::<str>().and_then(|r| r.ok()).unwrap_or("");
+                if let Err(e) = sink.forward(&event, key).await {
+                    eprintln!("Failed to forward event to sink: {e}");
+                    return ProcessOutcome::Failed;
+                }
+            }
 
-    loop {
-        match consumer.recv().await {
-            Ok(msg) => {
-                backoff = INITIAL_BACKOFF;
+            ProcessOutcome::Processed
+        }
+        Err(e) => {
+            eprintln!("Failed to parse CDC payload: {e}");
 
-                let topic = msg.topic();
-                let partition = msg.partition();
-                let offset = msg.offset();
+            if let Some(kafka_ts) = msg.timestamp().to_millis() {
+                lag_tracker.record((now_ms() - kafka_ts).max(0) as u64);
+            }
 
-                let payload = msg
-                    .payload_view::<str>()
-                    .and_then(|r| r.ok())
-                    .unwrap_or("<no payload>");
+            ProcessOutcome::Failed
+        }
+    }
+}
 
-                println!("[{topic}  p:{partition}  o:{offset}]");
-                println!("{payload}\n");
+/// Receive one message, process it, and run the periodic commit/lag-report
+/// checks, all as a single future.
+///
+/// Kept as one `async fn` (rather than splitting `consumer.recv()` out into
+/// its own `select!` branch) so the entire cycle — including a downstream
+/// sink produce that can stall — stays racing against the shutdown signals
+/// in the caller's `select!` for as long as it runs.
+#[allow(clippy::too_many_arguments)]
+async fn receive_and_process(
+    consumer: &StreamConsumer<CdcContext>,
+    lag_tracker: &LagTracker,
+    sink: Option<&Sink>,
+    backoff: &mut Duration,
+    last_sync_commit: &mut tokio::time::Instant,
+    last_lag_report: &mut tokio::time::Instant,
+) {
+    match consumer.recv().await {
+        Ok(msg) => {
+            *backoff = INITIAL_BACKOFF;
+
+            match handle_message(&msg, lag_tracker, sink).await {
+                ProcessOutcome::Processed => {
+                    if let Err(e) = consumer.commit_message(&msg, CommitMode::Async) {
+                        eprintln!("Failed to commit offset: {e}");
+                    }
+                }
+                ProcessOutcome::Failed => {
+                    eprintln!("Skipping commit, message will be redelivered");
+                }
             }
 
-            Err(KafkaError::MessageConsumption(RDKafkaErrorCode::UnknownTopicOrPartition)) => {
-                eprintln!(
-                    "Topic not yet available, retrying in {}s... \
-                     (waiting for Debezium to create CDC topics)",
-                    backoff.as_secs()
-                );
-                tokio::time::sleep(backoff).await;
-                backoff = (backoff * 2).min(MAX_BACKOFF);
+            if last_sync_commit.elapsed() >= SYNC_COMMIT_INTERVAL {
+                if let Err(e) = consumer.commit_consumer_state(CommitMode::Sync) {
+                    eprintln!("Periodic sync commit failed: {e}");
+                }
+                *last_sync_commit = tokio::time::Instant::now();
             }
 
-            Err(KafkaError::PartitionEOF(partition)) => {
-                eprintln!("Reached end of partition {partition}, waiting for new messages...");
+            if last_lag_report.elapsed() >= latency::REPORT_INTERVAL {
+                lag_tracker.report();
+                *last_lag_report = tokio::time::Instant::now();
             }
+        }
 
-            Err(KafkaError::MessageConsumptionFatal(code)) => {
-                eprintln!("Fatal consumer error: {code}");
-                std::process::exit(1);
-            }
+        Err(KafkaError::MessageConsumption(RDKafkaErrorCode::UnknownTopicOrPartition)) => {
+            eprintln!(
+                "Topic not yet available, retrying in {}s... \
+                 (waiting for Debezium to create CDC topics)",
+                backoff.as_secs()
+            );
+            tokio::time::sleep(*backoff).await;
+            *backoff = (*backoff * 2).min(MAX_BACKOFF);
+        }
 
-            Err(e) => {
-                eprintln!("Consumer error: {e}");
+        Err(KafkaError::PartitionEOF(partition)) => {
+            eprintln!("Reached end of partition {partition}, waiting for new messages...");
+        }
+
+        Err(KafkaError::MessageConsumptionFatal(code)) => {
+            eprintln!("Fatal consumer error: {code}");
+            std::process::exit(1);
+        }
+
+        Err(e) => {
+            eprintln!("Consumer error: {e}");
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    println!("Connecting to {}...", args.brokers);
+
+    let context = CdcContext::default();
+    let consumer: Arc<StreamConsumer<CdcContext>> = Arc::new(
+        ClientConfig::new()
+            .set("bootstrap.servers", &args.brokers)
+            .set("group.id", &args.group_id)
+            .set("auto.offset.reset", &args.offset_reset)
+            .set("enable.auto.commit", "false")
+            .create_with_context(context.clone())
+            .expect("Failed to create consumer"),
+    );
+    context.set_consumer(&consumer);
+
+    let topics: Vec<&str> = args.topics.iter().map(String::as_str).collect();
+    consumer
+        .subscribe(&topics)
+        .expect("Failed to subscribe to topics");
+
+    println!("Subscribed to: {}", args.topics.join(", "));
+    println!("Waiting for CDC events...\n");
+
+    let sink = match &args.sink_topic {
+        Some(topic) => {
+            println!("Forwarding change events to sink topic: {topic}");
+            let sink = Sink::new(&args.brokers, topic.clone())
+                .expect("Failed to create sink producer");
+            Some(sink)
+        }
+        None => None,
+    };
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut last_sync_commit = tokio::time::Instant::now();
+    let mut last_lag_report = tokio::time::Instant::now();
+    let lag_tracker = LagTracker::new();
+
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .expect("Failed to install SIGTERM handler");
+
+    // The whole receive-process-commit cycle for one message (including an
+    // unbounded sink produce) lives inside a single future so it keeps
+    // racing against the shutdown signals for its entire duration, not just
+    // while waiting on `consumer.recv()`. Otherwise a stuck downstream
+    // produce could block shutdown indefinitely.
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("Received SIGINT, shutting down...");
+                break;
+            }
+            _ = sigterm.recv() => {
+                println!("Received SIGTERM, shutting down...");
+                break;
             }
+            () = receive_and_process(
+                &consumer,
+                &lag_tracker,
+                sink.as_ref(),
+                &mut backoff,
+                &mut last_sync_commit,
+                &mut last_lag_report,
+            ) => {}
         }
     }
+
+    println!("Committing final offsets before exit...");
+    if let Err(e) = consumer.commit_consumer_state(CommitMode::Sync) {
+        eprintln!("Final offset commit failed: {e}");
+    }
 }
@@ -0,0 +1,59 @@
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use serde::Serialize;
+
+use crate::cdc::ChangeEvent;
+
+/// A normalized change record republished to the sink topic, decoupled
+/// from the Debezium envelope shape.
+#[derive(Debug, Serialize)]
+struct SinkRecord<'a> {
+    op: String,
+    table: &'a str,
+    key: &'a str,
+    after: &'a Option<serde_json::Value>,
+    ts_ms: i64,
+}
+
+/// Republishes parsed [`ChangeEvent`]s to a downstream topic, keyed by
+/// primary key so per-row ordering is preserved across partitions.
+pub struct Sink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl Sink {
+    pub fn new(brokers: &str, topic: String) -> Result<Self, rdkafka::error::KafkaError> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()?;
+        Ok(Self { producer, topic })
+    }
+
+    /// Serialize and send one change event, keyed by its primary key.
+    ///
+    /// Returns once the broker has acknowledged the produce, so callers
+    /// can commit the source offset only after this resolves.
+    pub async fn forward(&self, event: &ChangeEvent, key: &str) -> Result<(), String> {
+        let record = SinkRecord {
+            op: event.op.to_string(),
+            table: &event.source.table,
+            key,
+            after: &event.after,
+            ts_ms: event.ts_ms,
+        };
+
+        let payload = serde_json::to_vec(&record).map_err(|e| e.to_string())?;
+
+        self.producer
+            .send(
+                FutureRecord::to(&self.topic).key(key).payload(&payload),
+                Timeout::Never,
+            )
+            .await
+            .map_err(|(e, _msg)| e.to_string())?;
+
+        Ok(())
+    }
+}